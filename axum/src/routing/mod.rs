@@ -0,0 +1,55 @@
+mod path_router;
+pub mod normalize_path;
+mod router_urls;
+mod url_params;
+
+use path_router::PathRouter;
+
+pub use router_urls::RouterUrls;
+
+/// Param name reserved for the "rest of path" captured when one router is nested inside
+/// another.
+pub(crate) const NEST_TAIL_PARAM: &str = "__private__axum_nest_tail_param";
+
+/// A lightweight, pattern-based router: register handlers by path with [`Router::route`], then
+/// dispatch by matching the incoming request's path against those patterns.
+///
+/// [`Router::urls`] hands back a [`RouterUrls`] for turning a registered pattern back into a
+/// concrete URL.
+pub struct Router<S = ()> {
+    path_router: PathRouter<S>,
+}
+
+impl<S> Default for Router<S> {
+    fn default() -> Self {
+        Self {
+            path_router: PathRouter::new(),
+        }
+    }
+}
+
+impl<S> Router<S> {
+    /// Creates a new `Router` with no routes registered.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `handler` to be served at `path`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `path` isn't a valid route pattern, or if it conflicts with a route already
+    /// registered on this router.
+    pub fn route(mut self, path: &str, handler: S) -> Self {
+        self.path_router
+            .insert(path, handler)
+            .unwrap_or_else(|err| panic!("Invalid route {path:?}: {err}"));
+        self
+    }
+
+    /// Returns a [`RouterUrls`] for generating concrete URLs from the patterns registered on
+    /// this router.
+    pub fn urls(&self) -> RouterUrls {
+        RouterUrls::new(self.path_router.params().clone())
+    }
+}
@@ -0,0 +1,211 @@
+use http::{
+    uri::{PathAndQuery, Uri},
+    Request,
+};
+use std::{
+    borrow::Cow,
+    task::{Context, Poll},
+};
+use tower_layer::Layer;
+use tower_service::Service;
+
+/// Which normalization [`NormalizePath`] should apply to a request's path.
+///
+/// This only ever rewrites the path component of the URI; the query string, if any, is always
+/// preserved as-is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrailingSlash {
+    /// Strip a trailing slash, e.g. `/users/` becomes `/users`.
+    ///
+    /// The root path `/` is never trimmed away.
+    Trim,
+    /// Ensure exactly one trailing slash, e.g. `/users` becomes `/users/`.
+    Always,
+    /// Leave the trailing slash as-is; only collapse repeated `/` into a single `/`.
+    MergeOnly,
+}
+
+/// [`Layer`] that applies [`NormalizePath`], which normalizes a request's path before it reaches
+/// the router.
+///
+/// This lets routes be registered without worrying about whether callers include a trailing
+/// slash, avoiding surprising 404s for `/users/` vs `/users`. Repeated slashes (`/users//1`) are
+/// always collapsed into one, regardless of `mode`.
+///
+/// # Example
+///
+/// ```no_run
+/// use axum::routing::normalize_path::{NormalizePath, NormalizePathLayer, TrailingSlash};
+/// use tower_layer::Layer;
+///
+/// fn trim_trailing_slashes<S>(inner: S) -> NormalizePath<S> {
+///     NormalizePathLayer::new(TrailingSlash::Trim).layer(inner)
+/// }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct NormalizePathLayer {
+    mode: TrailingSlash,
+}
+
+impl NormalizePathLayer {
+    /// Create a new [`NormalizePathLayer`] that normalizes paths according to `mode`.
+    pub fn new(mode: TrailingSlash) -> Self {
+        Self { mode }
+    }
+}
+
+impl<S> Layer<S> for NormalizePathLayer {
+    type Service = NormalizePath<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        NormalizePath {
+            inner,
+            mode: self.mode,
+        }
+    }
+}
+
+/// Middleware that normalizes a request's URI path before calling the inner service.
+///
+/// See [`NormalizePathLayer`] for more details.
+#[derive(Debug, Clone, Copy)]
+pub struct NormalizePath<S> {
+    inner: S,
+    mode: TrailingSlash,
+}
+
+impl<S> NormalizePath<S> {
+    /// Create a new [`NormalizePath`] that normalizes paths according to `mode` before calling
+    /// `inner`.
+    pub fn new(inner: S, mode: TrailingSlash) -> Self {
+        Self { inner, mode }
+    }
+}
+
+impl<ReqBody, S> Service<Request<ReqBody>> for NormalizePath<S>
+where
+    S: Service<Request<ReqBody>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = S::Future;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: Request<ReqBody>) -> Self::Future {
+        normalize_uri_path(req.uri_mut(), self.mode);
+        self.inner.call(req)
+    }
+}
+
+fn normalize_uri_path(uri: &mut Uri, mode: TrailingSlash) {
+    let Some(normalized) = normalize_path(uri.path(), mode) else {
+        return;
+    };
+
+    let mut parts = uri.clone().into_parts();
+    let new_path_and_query = match parts.path_and_query.as_ref().and_then(PathAndQuery::query) {
+        Some(query) => format!("{normalized}?{query}"),
+        None => normalized.into_owned(),
+    };
+
+    if let Ok(path_and_query) = PathAndQuery::try_from(new_path_and_query) {
+        parts.path_and_query = Some(path_and_query);
+        if let Ok(new_uri) = Uri::from_parts(parts) {
+            *uri = new_uri;
+        }
+    }
+}
+
+/// Normalizes `path` according to `mode`, collapsing any repeated `/` along the way. Returns
+/// `None` if `path` is already normalized, so callers can skip rebuilding the URI.
+fn normalize_path(path: &str, mode: TrailingSlash) -> Option<Cow<'_, str>> {
+    let mut out = String::with_capacity(path.len());
+    let mut prev_was_slash = false;
+    for c in path.chars() {
+        if c == '/' {
+            if prev_was_slash {
+                continue;
+            }
+            prev_was_slash = true;
+        } else {
+            prev_was_slash = false;
+        }
+        out.push(c);
+    }
+
+    match mode {
+        TrailingSlash::MergeOnly => {}
+        TrailingSlash::Trim => {
+            while out.len() > 1 && out.ends_with('/') {
+                out.pop();
+            }
+        }
+        TrailingSlash::Always => {
+            if !out.ends_with('/') {
+                out.push('/');
+            }
+        }
+    }
+
+    if out.is_empty() {
+        out.push('/');
+    }
+
+    if out == path {
+        None
+    } else {
+        Some(Cow::Owned(out))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn normalize(path: &str, mode: TrailingSlash) -> String {
+        normalize_path(path, mode).map_or_else(|| path.to_owned(), Cow::into_owned)
+    }
+
+    #[test]
+    fn root_is_never_trimmed() {
+        assert_eq!(normalize("/", TrailingSlash::Trim), "/");
+    }
+
+    #[test]
+    fn trim_strips_a_trailing_slash() {
+        assert_eq!(normalize("/users/", TrailingSlash::Trim), "/users");
+        assert_eq!(normalize("/users", TrailingSlash::Trim), "/users");
+    }
+
+    #[test]
+    fn always_ensures_exactly_one_trailing_slash() {
+        assert_eq!(normalize("/users", TrailingSlash::Always), "/users/");
+        assert_eq!(normalize("/users/", TrailingSlash::Always), "/users/");
+    }
+
+    #[test]
+    fn merge_only_collapses_repeated_slashes_but_leaves_trailing_slash_alone() {
+        assert_eq!(
+            normalize("/users//1", TrailingSlash::MergeOnly),
+            "/users/1"
+        );
+        assert_eq!(normalize("/users/", TrailingSlash::MergeOnly), "/users/");
+        assert_eq!(normalize("/users", TrailingSlash::MergeOnly), "/users");
+    }
+
+    #[test]
+    fn repeated_slashes_are_collapsed_under_every_mode() {
+        assert_eq!(normalize("/users//1//", TrailingSlash::Trim), "/users/1");
+        assert_eq!(normalize("/users//1", TrailingSlash::Always), "/users/1/");
+    }
+
+    #[test]
+    fn already_normalized_paths_return_none() {
+        assert!(normalize_path("/users", TrailingSlash::Trim).is_none());
+        assert!(normalize_path("/users/", TrailingSlash::Always).is_none());
+        assert!(normalize_path("/", TrailingSlash::Trim).is_none());
+    }
+}
@@ -0,0 +1,131 @@
+use super::url_params::NormalizePathParams;
+use matchit::Router as MatchitRouter;
+use std::collections::HashMap;
+
+/// Opaque id assigned to each route pattern registered with a [`PathRouter`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(super) struct RouteId(u32);
+
+/// The matchit routing tree for a single [`Router`](super::Router), paired with the param-name
+/// bookkeeping from [`NormalizePathParams`].
+///
+/// A single call to [`PathRouter::insert`] may register more than one matchit route for the same
+/// `path` -- see [`NormalizePathParams::normalize_route_params`] for when that happens (an
+/// optional trailing param registers both with and without that segment).
+pub(super) struct PathRouter<T> {
+    node: MatchitRouter<RouteId>,
+    routes: HashMap<RouteId, T>,
+    params: NormalizePathParams,
+    next_id: u32,
+}
+
+impl<T> PathRouter<T> {
+    pub(super) fn new() -> Self {
+        Self {
+            node: MatchitRouter::new(),
+            routes: HashMap::new(),
+            params: NormalizePathParams::default(),
+            next_id: 0,
+        }
+    }
+
+    /// Registers `route` under `path`, rolling back cleanly if any of the one or more matchit
+    /// routes `path` normalizes to (see [`NormalizePathParams::normalize_route_params`]) conflicts
+    /// with an already-registered route -- so a failed call never leaves `self.node` holding an
+    /// entry with no corresponding `self.routes` handler, or `self.params` holding bookkeeping for
+    /// a registration matchit didn't fully accept.
+    pub(super) fn insert(&mut self, path: &str, route: T) -> Result<(), matchit::InsertError> {
+        let id = RouteId(self.next_id);
+
+        let (normalized, entry) = self.params.normalize_route_params(path);
+
+        let mut inserted = Vec::with_capacity(normalized.len());
+        for normalized_path in &normalized {
+            if let Err(err) = self.node.insert(normalized_path.clone(), id) {
+                for already_inserted in inserted {
+                    self.node.remove(already_inserted);
+                }
+                return Err(err);
+            }
+            inserted.push(normalized_path.clone());
+        }
+
+        self.next_id += 1;
+        self.params.commit_normalized_routes(&normalized, entry);
+        self.routes.insert(id, route);
+        Ok(())
+    }
+
+    pub(super) fn params(&self) -> &NormalizePathParams {
+        &self.params
+    }
+
+    /// Looks up the route registered for `path`, returning its [`RouteId`] and the matched path
+    /// params. Exists mainly so tests can exercise the real `matchit::Router::at` and catch
+    /// normalization bugs that only show up at match time (a normalized pattern matchit treats
+    /// as a literal, for example).
+    #[cfg(test)]
+    fn at<'p>(&self, path: &'p str) -> Option<(RouteId, matchit::Params<'_, 'p>)> {
+        self.node.at(path).ok().map(|matched| (*matched.value, matched.params))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn colon_syntax_route_matches_a_real_request_path() {
+        let mut router = PathRouter::<()>::new();
+        router.insert("/items/:id", ()).unwrap();
+
+        let (_, params) = router.at("/items/42").expect("route should match");
+        assert_eq!(params.get("axum_internal_param_2"), Some("42"));
+    }
+
+    #[test]
+    fn brace_syntax_route_matches_a_real_request_path() {
+        let mut router = PathRouter::<()>::new();
+        router.insert("/items/{id}", ()).unwrap();
+
+        let (_, params) = router.at("/items/42").expect("route should match");
+        assert_eq!(params.get("axum_internal_param_2_0"), Some("42"));
+    }
+
+    #[test]
+    fn catch_all_routes_match_for_both_syntaxes() {
+        let mut legacy = PathRouter::<()>::new();
+        legacy.insert("/files/*rest", ()).unwrap();
+        assert!(legacy.at("/files/a/b").is_some());
+
+        let mut brace = PathRouter::<()>::new();
+        brace.insert("/files/{*rest}", ()).unwrap();
+        assert!(brace.at("/files/a/b").is_some());
+    }
+
+    #[test]
+    fn optional_trailing_param_matches_with_and_without_the_segment() {
+        let mut router = PathRouter::<()>::new();
+        router.insert("/items/{id?}", ()).unwrap();
+
+        assert!(router.at("/items").is_some());
+        assert!(router.at("/items/42").is_some());
+    }
+
+    #[test]
+    fn failed_insert_rolls_back_routes_it_already_registered() {
+        let mut router = PathRouter::<&'static str>::new();
+        router.insert("/items", "static").unwrap();
+
+        // `/items/{id?}` normalizes to two matchit routes: `/items/{axum_internal_param_2_0}`
+        // (no conflict, registers fine) and `/items` (conflicts with the route above). The first
+        // insert succeeding before the second fails must not leave `/items/42` routable to a
+        // handler that was never actually committed.
+        assert!(router.insert("/items/{id?}", "optional").is_err());
+
+        assert!(router.at("/items/42").is_none());
+        let (id, _) = router.at("/items").expect("the original route must still match");
+        assert_eq!(router.routes.get(&id), Some(&"static"));
+        assert_eq!(router.routes.len(), 1);
+    }
+}
@@ -0,0 +1,77 @@
+use super::url_params::{NormalizePathParams, UriForError};
+use std::collections::HashMap;
+
+/// A handle for generating concrete URLs from route patterns registered on a [`Router`], given
+/// typed param values -- similar to Rocket's `uri!`.
+///
+/// Obtained via [`Router::urls`].
+///
+/// [`Router`]: super::Router
+/// [`Router::urls`]: super::Router::urls
+#[derive(Clone, Debug, Default)]
+pub struct RouterUrls {
+    params: NormalizePathParams,
+}
+
+impl RouterUrls {
+    pub(super) fn new(params: NormalizePathParams) -> Self {
+        Self { params }
+    }
+
+    /// Builds a concrete URL for the route registered as `pattern` (e.g. `/users/{id}`),
+    /// substituting `params` for each named path param and percent-encoding each value.
+    ///
+    /// Catch-all param values may contain `/`, which is left un-encoded so multi-segment values
+    /// round-trip; named param values always have `/` encoded.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `pattern` was never registered with this router, a required param is
+    /// missing from `params`, or `params` contains a param that isn't part of `pattern`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use axum::Router;
+    /// use std::collections::HashMap;
+    ///
+    /// let app: Router = Router::new().route("/users/{id}", ());
+    /// let urls = app.urls();
+    ///
+    /// let url = urls
+    ///     .uri_for("/users/{id}", &HashMap::from([("id", "42")]))
+    ///     .unwrap();
+    /// assert_eq!(url, "/users/42");
+    /// ```
+    pub fn uri_for(
+        &self,
+        pattern: &str,
+        params: &HashMap<&str, &str>,
+    ) -> Result<String, UriForError> {
+        self.params.uri_for(pattern, params)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::Router;
+    use std::collections::HashMap;
+
+    #[test]
+    fn router_urls_is_reachable_from_a_registered_route() {
+        let app: Router<()> = Router::new().route("/users/{id}", ());
+        let urls = app.urls();
+
+        let url = urls
+            .uri_for("/users/{id}", &HashMap::from([("id", "42")]))
+            .unwrap();
+        assert_eq!(url, "/users/42");
+    }
+
+    #[test]
+    fn uri_for_errors_on_an_unregistered_pattern() {
+        let app: Router<()> = Router::new().route("/users/{id}", ());
+        let err = app.urls().uri_for("/posts/{id}", &HashMap::new());
+        assert!(err.is_err());
+    }
+}
@@ -1,7 +1,12 @@
 use crate::util::{ByteStr, PercentDecodedByteStr};
 use http::Extensions;
 use matchit::Params;
-use std::{borrow::Cow, collections::HashMap, sync::Arc};
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    sync::Arc,
+};
 
 pub(crate) enum UrlParams {
     Params(Vec<(ByteStr, PercentDecodedByteStr)>),
@@ -72,57 +77,142 @@ pub(super) fn insert_url_params(
 /// It also supports mapping back to the original param names required by `Path` and `MatchedPath`.
 ///
 /// Ideally matchit would handle this automatically (https://github.com/ibraheemdev/matchit/issues/13)
+///
+/// Both the legacy `:name` / `*name` syntax and matchit's newer `{name}` / `{*name}` brace syntax
+/// are accepted as *input*. Only one of the two is ever valid against a given `matchit::Router`
+/// version, though -- e.g. matchit 0.7 treats `{` as a literal character, while matchit 0.8+
+/// treats `:` that way instead -- so whichever syntax the caller wrote, the normalized string
+/// actually handed to matchit is always rewritten to brace form (the syntax the `matchit`
+/// version this crate depends on understands; see [`normalize_segment`]). The brace syntax is
+/// also unambiguous when mixed with literal text within a segment (e.g. `/file.{ext}`). Both
+/// input syntaxes name params after their segment index, but brace params also carry their
+/// position within the segment, so the internal names differ between the two syntaxes: `/:a`
+/// becomes `/{axum_internal_param_1}` while `/{a}` becomes `/{axum_internal_param_1_0}`.
+///
+/// A final param may also be marked optional with `{name?}`, in which case two routes are
+/// registered for the one `original_path` -- one with the trailing param segment, one without --
+/// so a single handler can serve both `/items` and `/items/{id}`.
+///
+/// Finally, `original_path` is also indexed by [`NormalizePathParams::uri_for`] to support
+/// reverse URL generation: building a concrete URL from a route pattern plus typed params.
 #[derive(Default, Clone, Debug)]
 pub(super) struct NormalizePathParams {
     map: Arc<HashMap<String, OriginalPathAndNormalizedParams>>,
+    by_original_path: Arc<HashMap<String, OriginalPathAndNormalizedParams>>,
 }
 
 #[derive(Clone, Debug)]
-struct OriginalPathAndNormalizedParams {
+pub(super) struct OriginalPathAndNormalizedParams {
     original_path: String,
     normalized_params: HashMap<String, String>,
+    last_param_optional: bool,
 }
 
 const PARAM_PREFIX: &str = "axum_internal_param_";
 
 impl NormalizePathParams {
-    pub(super) fn normalize_route_params(&mut self, path: &str) -> String {
-        let mut normalized_params = HashMap::<String, String>::new();
+    /// Normalizes `path`, returning the normalized route(s) that should be registered with the
+    /// underlying matchit router, paired with the bookkeeping [`commit_normalized_routes`] needs
+    /// to record them. This is usually a single route, except when `path` ends in an optional
+    /// param (`{name?}`), in which case both the route with and without that trailing segment are
+    /// returned.
+    ///
+    /// This is a pure computation -- it doesn't mutate `self` -- so a caller can attempt to
+    /// register the returned routes with matchit first and only call [`commit_normalized_routes`]
+    /// once every one of them has actually succeeded, keeping a partially-conflicting
+    /// registration from leaving `self` out of sync with the matchit router.
+    ///
+    /// [`commit_normalized_routes`]: Self::commit_normalized_routes
+    pub(super) fn normalize_route_params(
+        &self,
+        path: &str,
+    ) -> (Vec<String>, OriginalPathAndNormalizedParams) {
+        let segments = path.split('/').collect::<Vec<_>>();
+        let last_segment_idx = segments.len().saturating_sub(1);
 
-        let normalized_path = path
-            .split('/')
-            .enumerate()
-            .map(|(idx, segment)| -> Cow<_> {
-                if let Some(param) = segment.strip_prefix(':') {
-                    let normalized_param_name = format!("{}{}", PARAM_PREFIX, idx);
+        let optional_last_param_name = segments[last_segment_idx]
+            .strip_prefix('{')
+            .and_then(|rest| rest.strip_suffix("?}"))
+            .filter(|name| {
+                !name.is_empty()
+                    && !name.starts_with('*')
+                    && !name.contains(|c| c == '{' || c == '}')
+            });
+
+        if let Some(param_name) = optional_last_param_name {
+            let mut normalized_params = HashMap::<String, String>::new();
 
-                    normalized_params.insert(normalized_param_name.clone(), param.into());
+            let prefix_segments = segments[..last_segment_idx]
+                .iter()
+                .enumerate()
+                .map(|(idx, segment)| normalize_segment(segment, idx, false, &mut normalized_params))
+                .collect::<Vec<_>>();
 
-                    format!(":{}", normalized_param_name).into()
-                } else if let Some(param) = segment.strip_prefix('*') {
-                    let normalized_param_name = format!("{}{}", PARAM_PREFIX, idx);
+            // `Vec::join` collapses a single-element vec to that element with no separator, so
+            // a route whose only segment is the optional param (`/{id?}`, `prefix_segments ==
+            // [""]`) would otherwise normalize to `""` instead of the root path `/`.
+            let without_last_param = if prefix_segments.len() <= 1 {
+                "/".to_owned()
+            } else {
+                prefix_segments.join("/")
+            };
 
-                    normalized_params.insert(normalized_param_name.clone(), param.into());
+            let normalized_param_name = format!("{}{}_0", PARAM_PREFIX, last_segment_idx);
+            normalized_params.insert(normalized_param_name.clone(), param_name.to_owned());
 
-                    format!("*{}", normalized_param_name).into()
-                } else {
-                    segment.into()
-                }
+            let mut segments_with_param = prefix_segments;
+            segments_with_param.push(format!("{{{normalized_param_name}}}"));
+            let with_last_param = segments_with_param.join("/");
+
+            let original_path_and_params = OriginalPathAndNormalizedParams {
+                original_path: path.to_owned(),
+                normalized_params,
+                last_param_optional: true,
+            };
+
+            return (
+                vec![with_last_param, without_last_param],
+                original_path_and_params,
+            );
+        }
+
+        let mut normalized_params = HashMap::<String, String>::new();
+
+        let normalized_path = segments
+            .into_iter()
+            .enumerate()
+            .map(|(idx, segment)| {
+                normalize_segment(segment, idx, idx == last_segment_idx, &mut normalized_params)
             })
             .collect::<Vec<_>>()
             .join("/");
 
+        let original_path_and_params = OriginalPathAndNormalizedParams {
+            original_path: path.to_owned(),
+            normalized_params,
+            last_param_optional: false,
+        };
+
+        (vec![normalized_path], original_path_and_params)
+    }
+
+    /// Records the routes previously computed by [`normalize_route_params`] now that every one of
+    /// them has been inserted into the matchit router, so `self` never holds bookkeeping for a
+    /// registration matchit didn't actually accept.
+    ///
+    /// [`normalize_route_params`]: Self::normalize_route_params
+    pub(super) fn commit_normalized_routes(
+        &mut self,
+        normalized: &[String],
+        entry: OriginalPathAndNormalizedParams,
+    ) {
+        let original_path = entry.original_path.clone();
         self.update_map(|map| {
-            map.insert(
-                normalized_path.clone(),
-                OriginalPathAndNormalizedParams {
-                    original_path: path.to_owned(),
-                    normalized_params,
-                },
-            );
+            for normalized_path in normalized {
+                map.insert(normalized_path.clone(), entry.clone());
+            }
         });
-
-        normalized_path
+        self.update_by_original_path(&original_path, entry);
     }
 
     pub(super) fn get_original_path(&self, matched_path: &str) -> &str {
@@ -140,14 +230,383 @@ impl NormalizePathParams {
 
     pub(super) fn merge(&mut self, other: Self) {
         self.update_map(|map| map.extend(other.map.as_ref().clone()));
+        Arc::make_mut(&mut self.by_original_path).extend(other.by_original_path.as_ref().clone());
+    }
+
+    /// Builds a concrete URL for the route registered under `original_path` (e.g. `/users/{id}`),
+    /// substituting `params` for each named path param and percent-encoding each value.
+    ///
+    /// Catch-all param values may contain `/`, which is left un-encoded so multi-segment values
+    /// round-trip; named param values always have `/` encoded.
+    pub(super) fn uri_for(
+        &self,
+        original_path: &str,
+        params: &HashMap<&str, &str>,
+    ) -> Result<String, UriForError> {
+        let entry = self
+            .by_original_path
+            .get(original_path)
+            .ok_or_else(|| UriForError::unknown_route(original_path))?;
+
+        let segments = original_path.split('/').collect::<Vec<_>>();
+        let last_segment_idx = segments.len().saturating_sub(1);
+        let mut unused_params: HashSet<&str> = params.keys().copied().collect();
+        let mut uri = String::with_capacity(original_path.len());
+
+        for (idx, segment) in segments.into_iter().enumerate() {
+            if idx > 0 {
+                uri.push('/');
+            }
+
+            let is_optional_last = entry.last_param_optional && idx == last_segment_idx;
+
+            for part in parse_original_segment(segment) {
+                match part {
+                    SegmentPart::Literal(text) => uri.push_str(text),
+                    SegmentPart::Param { name, catch_all } => match params.get(name) {
+                        Some(value) => {
+                            unused_params.remove(name);
+                            let encode_set = if catch_all {
+                                CATCH_ALL_ENCODE_SET
+                            } else {
+                                PATH_SEGMENT_ENCODE_SET
+                            };
+                            uri.extend(utf8_percent_encode(value, encode_set));
+                        }
+                        None if is_optional_last => {
+                            if uri.ends_with('/') {
+                                uri.pop();
+                            }
+                        }
+                        None => return Err(UriForError::missing_param(name)),
+                    },
+                }
+            }
+        }
+
+        if let Some(unexpected) = unused_params.into_iter().min() {
+            return Err(UriForError::unexpected_param(unexpected));
+        }
+
+        // Mirrors the `prefix_segments.len() <= 1` special case in `normalize_route_params`:
+        // if the only segment was the omitted optional param (pattern `/{id?}`), popping its
+        // leading `/` above leaves an empty string rather than the root path.
+        if uri.is_empty() {
+            uri.push('/');
+        }
+
+        Ok(uri)
     }
 
     fn update_map<F>(&mut self, f: F)
     where
         F: FnOnce(&mut HashMap<String, OriginalPathAndNormalizedParams>),
     {
-        let mut map = self.map.as_ref().clone();
-        f(&mut map);
-        self.map = Arc::new(map);
+        f(Arc::make_mut(&mut self.map));
+    }
+
+    fn update_by_original_path(&mut self, original_path: &str, entry: OriginalPathAndNormalizedParams) {
+        Arc::make_mut(&mut self.by_original_path).insert(original_path.to_owned(), entry);
+    }
+}
+
+/// Error returned when generating a URL via [`NormalizePathParams::uri_for`] fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UriForError {
+    /// No route was registered with the given pattern.
+    UnknownRoute { pattern: String },
+    /// The pattern has a named param that wasn't supplied.
+    MissingParam { name: String },
+    /// A supplied param doesn't appear in the pattern.
+    UnexpectedParam { name: String },
+}
+
+impl UriForError {
+    fn unknown_route(pattern: &str) -> Self {
+        Self::UnknownRoute {
+            pattern: pattern.to_owned(),
+        }
+    }
+
+    fn missing_param(name: &str) -> Self {
+        Self::MissingParam {
+            name: name.to_owned(),
+        }
+    }
+
+    fn unexpected_param(name: &str) -> Self {
+        Self::UnexpectedParam {
+            name: name.to_owned(),
+        }
+    }
+}
+
+impl fmt::Display for UriForError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownRoute { pattern } => write!(f, "no route registered for `{pattern}`"),
+            Self::MissingParam { name } => write!(f, "missing value for path param `{name}`"),
+            Self::UnexpectedParam { name } => {
+                write!(f, "`{name}` is not a path param of this route")
+            }
+        }
+    }
+}
+
+impl std::error::Error for UriForError {}
+
+const PATH_SEGMENT_ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+const CATCH_ALL_ENCODE_SET: &AsciiSet = &PATH_SEGMENT_ENCODE_SET.remove(b'/');
+
+enum SegmentPart<'a> {
+    Literal(&'a str),
+    Param { name: &'a str, catch_all: bool },
+}
+
+/// Parses a registered route's segment (e.g. `:id`, `*rest`, `{id}`, `{*rest}`, `{id?}`, or
+/// `file.{ext}`) back into its literal and param parts, mirroring [`normalize_segment`] so
+/// [`NormalizePathParams::uri_for`] can substitute params in the same places they were found.
+fn parse_original_segment(segment: &str) -> Vec<SegmentPart<'_>> {
+    if let Some(name) = segment.strip_prefix(':') {
+        return vec![SegmentPart::Param {
+            name,
+            catch_all: false,
+        }];
+    }
+    if let Some(name) = segment.strip_prefix('*') {
+        return vec![SegmentPart::Param {
+            name,
+            catch_all: true,
+        }];
+    }
+
+    let mut parts = Vec::new();
+    let mut rest = segment;
+
+    while let Some(brace_start) = rest.find('{') {
+        if brace_start > 0 {
+            parts.push(SegmentPart::Literal(&rest[..brace_start]));
+        }
+
+        let after_brace = &rest[brace_start + 1..];
+        let brace_end = after_brace
+            .find('}')
+            .expect("segment was validated when the route was registered");
+        let inner = &after_brace[..brace_end];
+
+        let (name, catch_all) = match inner.strip_prefix('*') {
+            Some(name) => (name, true),
+            None => (inner, false),
+        };
+        let name = name.strip_suffix('?').unwrap_or(name);
+
+        parts.push(SegmentPart::Param { name, catch_all });
+        rest = &after_brace[brace_end + 1..];
+    }
+
+    if !rest.is_empty() || parts.is_empty() {
+        parts.push(SegmentPart::Literal(rest));
+    }
+
+    parts
+}
+
+/// Normalizes a single path segment, recognizing the legacy whole-segment `:name` / `*name`
+/// prefixes as well as brace params (handled by [`normalize_brace_segment`]).
+///
+/// Whichever syntax `segment` uses, the string returned here is always in matchit's brace form
+/// (`{name}` / `{*name}`) -- see the note on [`NormalizePathParams`] for why.
+fn normalize_segment(
+    segment: &str,
+    idx: usize,
+    is_last_segment: bool,
+    normalized_params: &mut HashMap<String, String>,
+) -> String {
+    if let Some(param) = segment.strip_prefix(':') {
+        assert!(
+            !param.ends_with('?'),
+            "Invalid route: `:name` does not support an optional `?` marker, use `{{name?}}` \
+             instead, found in segment {segment:?}",
+        );
+        let normalized_param_name = format!("{}{}", PARAM_PREFIX, idx);
+        normalized_params.insert(normalized_param_name.clone(), param.to_owned());
+        format!("{{{normalized_param_name}}}")
+    } else if let Some(param) = segment.strip_prefix('*') {
+        let normalized_param_name = format!("{}{}", PARAM_PREFIX, idx);
+        normalized_params.insert(normalized_param_name.clone(), param.to_owned());
+        format!("{{*{normalized_param_name}}}")
+    } else if segment.contains('{') {
+        normalize_brace_segment(segment, idx, is_last_segment, normalized_params)
+    } else {
+        segment.to_owned()
+    }
+}
+
+/// Normalizes a single path segment that contains one or more `{name}` / `{*name}` brace params,
+/// rebuilding the segment so that any surrounding literal text (e.g. the `file.` in `file.{ext}`)
+/// is preserved around the normalized param name.
+fn normalize_brace_segment(
+    segment: &str,
+    idx: usize,
+    is_last_segment: bool,
+    normalized_params: &mut HashMap<String, String>,
+) -> String {
+    let mut out = String::with_capacity(segment.len());
+    let mut rest = segment;
+    let mut param_idx_in_segment = 0;
+
+    while let Some(brace_start) = rest.find('{') {
+        out.push_str(&rest[..brace_start]);
+
+        let after_brace = &rest[brace_start + 1..];
+        let brace_end = after_brace
+            .find('}')
+            .unwrap_or_else(|| panic!("Invalid route: unterminated `{{` in segment {segment:?}"));
+        let inner = &after_brace[..brace_end];
+
+        let (name, is_catch_all) = match inner.strip_prefix('*') {
+            Some(name) => (name, true),
+            None => (inner, false),
+        };
+        assert!(
+            !name.is_empty(),
+            "Invalid route: empty param name in segment {segment:?}",
+        );
+        assert!(
+            !name.ends_with('?'),
+            "Invalid route: `?` can only mark a param optional when it is the sole content of \
+             the final path segment (e.g. `/items/{{id?}}`), found in segment {segment:?}",
+        );
+
+        if is_catch_all {
+            assert!(
+                is_last_segment && out.is_empty() && after_brace[brace_end + 1..].is_empty(),
+                "Invalid route: `{{*{name}}}` catch-all params must occupy the whole final segment, \
+                 found in segment {segment:?}",
+            );
+        }
+
+        let normalized_param_name = format!("{}{}_{}", PARAM_PREFIX, idx, param_idx_in_segment);
+        param_idx_in_segment += 1;
+        normalized_params.insert(normalized_param_name.clone(), name.to_owned());
+
+        if is_catch_all {
+            out.push_str("{*");
+            out.push_str(&normalized_param_name);
+            out.push('}');
+        } else {
+            out.push('{');
+            out.push_str(&normalized_param_name);
+            out.push('}');
+        }
+
+        rest = &after_brace[brace_end + 1..];
+    }
+
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn normalize(path: &str) -> Vec<String> {
+        let mut params = NormalizePathParams::default();
+        let (routes, entry) = params.normalize_route_params(path);
+        params.commit_normalized_routes(&routes, entry);
+        routes
+    }
+
+    #[test]
+    fn brace_and_legacy_syntax_both_normalize_to_matchit_brace_form() {
+        // Both input syntaxes are accepted, but the string actually handed to `matchit` is
+        // always brace form -- matchit versions that understand `:name` don't understand
+        // `{name}` and vice versa, so only one form can ever be registered for real.
+        assert_eq!(
+            normalize("/:a/:b"),
+            vec!["/{axum_internal_param_1}/{axum_internal_param_2}"]
+        );
+        assert_eq!(
+            normalize("/{a}/{b}"),
+            vec!["/{axum_internal_param_1_0}/{axum_internal_param_2_0}"]
+        );
+        assert_eq!(normalize("/*rest"), vec!["/{*axum_internal_param_1}"]);
+        assert_eq!(normalize("/{*rest}"), vec!["/{*axum_internal_param_1_0}"]);
+    }
+
+    #[test]
+    fn brace_param_can_be_mixed_with_literal_text_in_a_segment() {
+        let normalized = normalize("/file.{ext}");
+        assert_eq!(normalized.len(), 1);
+        assert!(normalized[0].starts_with("/file."));
+    }
+
+    #[test]
+    fn catch_all_must_occupy_the_whole_final_segment() {
+        let result = std::panic::catch_unwind(|| normalize("/file.{*rest}"));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn optional_trailing_param_registers_both_routes() {
+        let routes = normalize("/items/{id?}");
+        assert_eq!(routes, vec!["/items/{axum_internal_param_2_0}", "/items"]);
+    }
+
+    #[test]
+    fn optional_trailing_param_on_the_root_segment_does_not_produce_an_empty_route() {
+        let routes = normalize("/{id?}");
+        assert_eq!(routes, vec!["/{axum_internal_param_1_0}", "/"]);
+    }
+
+    #[test]
+    fn optional_marker_is_rejected_outside_the_sole_final_segment() {
+        for invalid in ["/{a?}/{b}", "/file.{ext?}", "/{*rest?}"] {
+            let result = std::panic::catch_unwind(|| normalize(invalid));
+            assert!(result.is_err(), "expected {invalid:?} to be rejected");
+        }
+    }
+
+    #[test]
+    fn uri_for_round_trips_brace_params() {
+        let mut params = NormalizePathParams::default();
+        let (routes, entry) = params.normalize_route_params("/users/{id}/{*rest}");
+        params.commit_normalized_routes(&routes, entry);
+
+        let uri = params
+            .uri_for(
+                "/users/{id}/{*rest}",
+                &HashMap::from([("id", "42"), ("rest", "a/b")]),
+            )
+            .unwrap();
+        assert_eq!(uri, "/users/42/a/b");
+    }
+
+    #[test]
+    fn uri_for_root_level_optional_param_omitted_is_the_root_path() {
+        let mut params = NormalizePathParams::default();
+        let (routes, entry) = params.normalize_route_params("/{id?}");
+        params.commit_normalized_routes(&routes, entry);
+
+        let uri = params.uri_for("/{id?}", &HashMap::new()).unwrap();
+        assert_eq!(uri, "/");
+    }
+
+    #[test]
+    fn uri_for_root_level_optional_param_supplied() {
+        let mut params = NormalizePathParams::default();
+        let (routes, entry) = params.normalize_route_params("/{id?}");
+        params.commit_normalized_routes(&routes, entry);
+
+        let uri = params
+            .uri_for("/{id?}", &HashMap::from([("id", "42")]))
+            .unwrap();
+        assert_eq!(uri, "/42");
     }
 }